@@ -0,0 +1,64 @@
+//! The `Context` is the main state-holding object for the game.
+
+use crate::error::GameResult;
+use crate::timer::{self, TimeContext, Timestep};
+
+/// A `Context` is an object that holds on to global resources.
+/// It basically tracks hardware state such as the screen, audio
+/// system, input, and so on.  Generally this is used by more or
+/// less everything else in the crate, `timer` included.
+#[derive(Debug)]
+pub struct Context {
+    pub(crate) timer_context: TimeContext,
+}
+
+impl Context {
+    /// Creates a new `Context`.
+    pub fn new() -> Context {
+        Context {
+            timer_context: TimeContext::new(),
+        }
+    }
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A builder object for creating a `Context`.
+#[derive(Debug, Clone)]
+pub struct ContextBuilder {
+    timestep: Timestep,
+}
+
+impl Default for ContextBuilder {
+    fn default() -> Self {
+        ContextBuilder {
+            timestep: Timestep::default(),
+        }
+    }
+}
+
+impl ContextBuilder {
+    /// Creates a new `ContextBuilder`.
+    pub fn new(_game_id: &str, _author: &str) -> ContextBuilder {
+        ContextBuilder::default()
+    }
+
+    /// Sets the `Timestep` mode the built `Context` will use to drive
+    /// `EventHandler::update()`/`draw()`; see `timer::Timestep`.
+    /// Defaults to `Timestep::Variable`.
+    pub fn timestep(mut self, timestep: Timestep) -> Self {
+        self.timestep = timestep;
+        self
+    }
+
+    /// Builds the `Context`.
+    pub fn build(self) -> GameResult<Context> {
+        let mut ctx = Context::new();
+        timer::set_timestep(&mut ctx, self.timestep);
+        Ok(ctx)
+    }
+}
@@ -0,0 +1,27 @@
+//! Error types and conversion functions.
+//!
+//! This is a pretty simple error type, since our main use for errors
+//! is just to pick them up and display them to the user.
+
+use std::error::Error;
+use std::fmt;
+
+/// An enum containing all kinds of game framework errors.
+#[derive(Debug)]
+pub enum GameError {
+    /// Something went wrong trying to run the event loop.
+    EventLoopError(String),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GameError::EventLoopError(ref s) => write!(f, "Event loop error: {}", s),
+        }
+    }
+}
+
+impl Error for GameError {}
+
+/// A convenient result type consisting of a return type and a `GameError`.
+pub type GameResult<T = ()> = Result<T, GameError>;
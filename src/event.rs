@@ -0,0 +1,90 @@
+//! The `event` module contains traits and structures to actually run
+//! your game mainloop and handle top-level state.
+
+use std::time;
+
+use crate::context::Context;
+use crate::error::GameResult;
+use crate::timer;
+
+/// A trait defining event callbacks.  This is the primary interface
+/// with `ggez`'s event loop: implement this trait for your game state
+/// and pass it to `event::run()`.
+pub trait EventHandler {
+    /// Called upon each logic update to the game.  This should be
+    /// where the game's logic takes place.
+    fn update(&mut self, ctx: &mut Context) -> GameResult<()>;
+
+    /// Called to do the drawing of your game.
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()>;
+
+    /// Called every frame to check whether the game should quit.
+    /// Returning `true` ends `event::run()`'s main loop.  Defaults to
+    /// `false`, i.e. keep running.
+    fn quit_event(&mut self, _ctx: &mut Context) -> bool {
+        false
+    }
+}
+
+/// Runs the game's main loop, calling the given `EventHandler`'s
+/// `update()` and `draw()` methods every frame until it requests
+/// that the game quit.
+///
+/// This is what actually drives `timer::Timestep`: it accumulates
+/// elapsed time into `residual_update_dt` via `TimeContext::tick()`
+/// and, when `timer::Timestep::Fixed` is selected, drains it in
+/// fixed-size chunks -- calling `update()` zero or more times -- then
+/// calls `draw()` exactly once per frame.  `timer::get_blend_factor()`
+/// can then be used in `draw()` to interpolate between the last two
+/// simulation steps.
+///
+/// When `timer::set_frame_pacing()` is enabled, the top of the loop
+/// sleeps until shortly before the next predicted vblank instead of
+/// running flat-out or yielding immediately, so input sampling and
+/// simulation happen as late as possible before `draw()`.
+///
+/// Each phase's actual duration -- the pacing sleep/yield, the
+/// `update()` call(s), and `draw()` -- is recorded as it happens, so
+/// `timer::get_idle_time()`, `timer::get_update_time()`, and
+/// `timer::get_draw_time()` report real numbers instead of always
+/// reading zero.
+pub fn run<S: EventHandler>(ctx: &mut Context, state: &mut S) -> GameResult<()> {
+    // Until we have a real windowing backend to query, assume 60 Hz.
+    let refresh_interval = time::Duration::from_secs(1) / 60;
+
+    loop {
+        let idle_start = time::Instant::now();
+        if timer::get_frame_pacing(ctx) {
+            ctx.timer_context.sleep_for_pacing(refresh_interval);
+        } else {
+            timer::yield_now();
+        }
+        ctx.timer_context.record_idle_time(idle_start.elapsed());
+
+        ctx.timer_context.tick();
+
+        match timer::get_timestep(ctx) {
+            timer::Timestep::Fixed(updates_per_sec) => {
+                let tick_rate = timer::f64_to_duration(1.0 / updates_per_sec);
+                while timer::drain_fixed_update_time(ctx, tick_rate) {
+                    let update_start = time::Instant::now();
+                    state.update(ctx)?;
+                    ctx.timer_context.record_update_time(update_start.elapsed());
+                }
+            }
+            timer::Timestep::Variable => {
+                let update_start = time::Instant::now();
+                state.update(ctx)?;
+                ctx.timer_context.record_update_time(update_start.elapsed());
+            }
+        }
+
+        let draw_start = time::Instant::now();
+        state.draw(ctx)?;
+        ctx.timer_context.record_draw_time(draw_start.elapsed());
+
+        if state.quit_event(ctx) {
+            return Ok(());
+        }
+    }
+}
@@ -76,6 +76,31 @@ where
     }
 }
 
+/// Selects how the game loop advances `EventHandler::update()`.
+///
+/// `Fixed` accumulates elapsed time into a residual and spends it in
+/// whole `updates_per_sec` steps, so the simulation advances
+/// deterministically no matter how fast or slow the machine is
+/// rendering. `Variable` calls `update()` once per frame with
+/// whatever delta has actually elapsed, which is simpler but makes
+/// the simulation's behavior depend on frame rate.
+///
+/// See <http://gafferongames.com/game-physics/fix-your-timestep/>
+/// for the rationale behind the fixed mode.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Timestep {
+    /// Advance the simulation in fixed-size steps, `updates_per_sec` times a second.
+    Fixed(f64),
+    /// Advance the simulation once per frame, using the real elapsed time.
+    Variable,
+}
+
+impl Default for Timestep {
+    fn default() -> Self {
+        Timestep::Variable
+    }
+}
+
 /// A structure that contains our time-tracking state.
 #[derive(Debug)]
 pub struct TimeContext {
@@ -84,11 +109,35 @@ pub struct TimeContext {
     frame_durations: LogBuffer<time::Duration>,
     residual_update_dt: time::Duration,
     frame_count: usize,
+    timestep: Timestep,
+    time_scale: f64,
+    paused: bool,
+    scaled_delta: time::Duration,
+    game_time_since_start: time::Duration,
+    frame_pacing: bool,
+    rolling_max_draw_time: time::Duration,
+    next_vblank: Option<time::Instant>,
+    update_durations: LogBuffer<time::Duration>,
+    draw_durations: LogBuffer<time::Duration>,
+    idle_durations: LogBuffer<time::Duration>,
 }
 
 // How many frames we log update times for.
 const TIME_LOG_FRAMES: usize = 200;
 
+// The rolling-max decay is expressed as a fraction of this scale, so
+// that `ROLLING_MAX_DECAY / ROLLING_MAX_SCALE` gives the fraction of
+// the previous rolling max that survives each frame.
+const ROLLING_MAX_SCALE: u32 = 1000;
+const ROLLING_MAX_DECAY: u32 = 930;
+
+// A small fixed safety margin subtracted from the sleep budget so we
+// wake up a bit before the deadline rather than risk oversleeping
+// past it.
+fn red_zone() -> time::Duration {
+    time::Duration::from_millis(2)
+}
+
 impl TimeContext {
     /// Creates a new `TimeContext` and initializes the start to this instant.
     pub fn new() -> TimeContext {
@@ -98,7 +147,68 @@ impl TimeContext {
             frame_durations: LogBuffer::new(TIME_LOG_FRAMES, time::Duration::new(0, 0)),
             residual_update_dt: time::Duration::from_secs(0),
             frame_count: 0,
+            timestep: Timestep::default(),
+            time_scale: 1.0,
+            paused: false,
+            scaled_delta: time::Duration::new(0, 0),
+            game_time_since_start: time::Duration::new(0, 0),
+            frame_pacing: false,
+            rolling_max_draw_time: time::Duration::new(0, 0),
+            next_vblank: None,
+            update_durations: LogBuffer::new(TIME_LOG_FRAMES, time::Duration::new(0, 0)),
+            draw_durations: LogBuffer::new(TIME_LOG_FRAMES, time::Duration::new(0, 0)),
+            idle_durations: LogBuffer::new(TIME_LOG_FRAMES, time::Duration::new(0, 0)),
+        }
+    }
+
+    /// Records how long the most recently completed `update()` call
+    /// took, for `timer::get_update_time()`.
+    pub(crate) fn record_update_time(&mut self, update_time: time::Duration) {
+        self.update_durations.push(update_time);
+    }
+
+    /// Records how long the most recently completed `draw()` (and its
+    /// present) took, for `timer::get_draw_time()`, and updates the
+    /// rolling estimate used by the frame pacer.  Spikes are absorbed
+    /// immediately, since the rolling max only ever increases to meet
+    /// a new high, but otherwise decay smoothly back down toward the
+    /// true cost of drawing.
+    pub(crate) fn record_draw_time(&mut self, draw_time: time::Duration) {
+        self.draw_durations.push(draw_time);
+
+        let draw_secs = duration_to_f64(draw_time);
+        let decayed_max =
+            duration_to_f64(self.rolling_max_draw_time) * f64::from(ROLLING_MAX_DECAY)
+                / f64::from(ROLLING_MAX_SCALE);
+        self.rolling_max_draw_time = f64_to_duration(draw_secs.max(decayed_max));
+    }
+
+    /// Records how long the loop spent sleeping or yielding between
+    /// frames, for `timer::get_idle_time()`.
+    pub(crate) fn record_idle_time(&mut self, idle_time: time::Duration) {
+        self.idle_durations.push(idle_time);
+    }
+
+    /// Sleeps until shortly before the next predicted vblank, so that
+    /// input sampling and simulation happen as late as possible before
+    /// `draw()` is called, cutting a frame of latency versus running
+    /// flat-out or yielding immediately after the previous present.
+    ///
+    /// `refresh_interval` is the display's refresh interval (e.g.
+    /// `1s / 60` for a 60 Hz display).
+    pub(crate) fn sleep_for_pacing(&mut self, refresh_interval: time::Duration) {
+        let now = time::Instant::now();
+        let next_vblank = match self.next_vblank {
+            Some(predicted) if predicted > now => predicted,
+            _ => now + refresh_interval,
+        };
+        let budget = self.rolling_max_draw_time + red_zone();
+        if let Some(sleep_until) = next_vblank.checked_sub(budget) {
+            if sleep_until > now {
+                thread::sleep(sleep_until - now);
+            }
         }
+        self.next_vblank = Some(next_vblank + refresh_interval);
     }
 
     /// Update the state of the TimeContext to record that
@@ -114,7 +224,14 @@ impl TimeContext {
         self.last_instant = now;
         self.frame_count += 1;
 
-        self.residual_update_dt += time_since_last;
+        self.scaled_delta = if self.paused {
+            time::Duration::new(0, 0)
+        } else {
+            f64_to_duration(duration_to_f64(time_since_last) * self.time_scale)
+        };
+        self.game_time_since_start += self.scaled_delta;
+
+        self.residual_update_dt += self.scaled_delta;
     }
 }
 
@@ -134,13 +251,111 @@ pub fn get_delta(ctx: &Context) -> time::Duration {
 /// Gets the average time of a frame, averaged
 /// over the last 200 frames.
 pub fn get_average_delta(ctx: &Context) -> time::Duration {
+    average_duration(&ctx.timer_context.frame_durations)
+}
+
+/// Gets the average time spent in `update()` per frame, averaged over
+/// the last 200 frames.
+///
+/// Together with `get_draw_time()` and `get_idle_time()`, this splits
+/// up where a frame's time actually goes -- e.g. "3 ms update, 8 ms
+/// draw, 5 ms idle" -- so you can tell whether you're CPU-, GPU-, or
+/// vsync-bound without pulling in an external profiler.
+pub fn get_update_time(ctx: &Context) -> time::Duration {
+    average_duration(&ctx.timer_context.update_durations)
+}
+
+/// Gets the average time spent in `draw()` (and its present) per
+/// frame, averaged over the last 200 frames.  See `get_update_time()`.
+pub fn get_draw_time(ctx: &Context) -> time::Duration {
+    average_duration(&ctx.timer_context.draw_durations)
+}
+
+/// Gets the average time spent sleeping or yielding between frames,
+/// averaged over the last 200 frames.  See `get_update_time()`.
+pub fn get_idle_time(ctx: &Context) -> time::Duration {
+    average_duration(&ctx.timer_context.idle_durations)
+}
+
+/// Averages the contents of a `LogBuffer` of durations.
+fn average_duration(buf: &LogBuffer<time::Duration>) -> time::Duration {
+    let init = time::Duration::new(0, 0);
+    let sum = buf.contents().iter().fold(init, |d1, d2| d1 + *d2);
+    sum / (buf.size as u32)
+}
+
+/// Frame-time statistics computed over the frame-time window, for
+/// building performance overlays or detecting stutter that a bare
+/// average FPS number hides.
+///
+/// The "1% low" and "0.1% low" fields are the mean of the slowest 1%
+/// and 0.1% of frames respectively, a standard way of summarizing how
+/// bad the worst stutters are without just reporting the single
+/// slowest outlier.
+#[derive(Debug, Copy, Clone)]
+pub struct FrameTimeStats {
+    /// The fastest frame in the window.
+    pub min: time::Duration,
+    /// The slowest frame in the window.
+    pub max: time::Duration,
+    /// The mean frame time over the window.
+    pub mean: time::Duration,
+    /// The mean of the slowest 1% of frames in the window.
+    pub one_percent_low: time::Duration,
+    /// The mean of the slowest 0.1% of frames in the window.
+    pub point_one_percent_low: time::Duration,
+}
+
+/// Returns min/max/mean/percentile-low frame-time statistics computed
+/// over the last 200 frames.
+///
+/// `LogBuffer` pre-fills its window with zero durations, so until the
+/// game has been running for 200 frames, `min` reads as zero and
+/// `mean` is pulled down by the zero slots rather than reflecting only
+/// the frames that have actually happened -- the same up-to-~3-second
+/// startup imprecision `get_fps()`/`get_average_delta()` already have.
+pub fn get_frame_time_stats(ctx: &Context) -> FrameTimeStats {
     let tc = &ctx.timer_context;
+    let mut sorted: Vec<time::Duration> = tc.frame_durations.contents().to_vec();
+    sorted.sort();
+
+    let mean = get_average_delta(ctx);
+    let min = sorted[0];
+    let max = sorted[sorted.len() - 1];
+    let one_percent_low = mean_of_slowest_fraction(&sorted, 0.01);
+    let point_one_percent_low = mean_of_slowest_fraction(&sorted, 0.001);
+
+    FrameTimeStats {
+        min,
+        max,
+        mean,
+        one_percent_low,
+        point_one_percent_low,
+    }
+}
+
+/// Returns the mean of the slowest `fraction` of frame times in a
+/// non-empty `sorted` (ascending) scratch copy of the frame-time
+/// window.
+fn mean_of_slowest_fraction(sorted: &[time::Duration], fraction: f64) -> time::Duration {
+    let count = cmp::max(1, (sorted.len() as f64 * fraction).round() as usize);
+    let slowest = &sorted[sorted.len() - count..];
     let init = time::Duration::new(0, 0);
-    let sum = tc.frame_durations
-        .contents()
-        .iter()
-        .fold(init, |d1, d2| d1 + *d2);
-    sum / (tc.frame_durations.size as u32)
+    let sum = slowest.iter().fold(init, |d1, d2| d1 + *d2);
+    sum / (slowest.len() as u32)
+}
+
+/// Returns the `p`th percentile frame time (e.g. `p = 99.0` for the
+/// 99th percentile) over the last 200 frames.  See
+/// `get_frame_time_stats()` for the startup imprecision this shares
+/// with the rest of the module.
+pub fn get_frame_time_percentile(ctx: &Context, p: f64) -> time::Duration {
+    let tc = &ctx.timer_context;
+    let mut sorted: Vec<time::Duration> = tc.frame_durations.contents().to_vec();
+    sorted.sort();
+    let clamped = p.max(0.0).min(100.0);
+    let index = ((clamped / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+    sorted[index]
 }
 
 /// A convenience function to convert a Rust `Duration` type
@@ -189,6 +404,97 @@ pub fn get_time_since_start(ctx: &Context) -> time::Duration {
     time::Instant::now() - tc.init_instant
 }
 
+/// Gets the time between the start of the last frame and the current
+/// one, scaled by `set_time_scale()` and zeroed out while paused.
+///
+/// Use this instead of `get_delta()` to drive physics and animation
+/// that should slow down, stop, or speed up with the game clock
+/// (pause menus, bullet-time effects, replay scrubbing) rather than
+/// always tracking real wall-clock time.
+pub fn get_scaled_delta(ctx: &Context) -> time::Duration {
+    ctx.timer_context.scaled_delta
+}
+
+/// Returns the accumulated game time since the game was initialized,
+/// i.e. `get_time_since_start()` with `set_time_scale()` and
+/// `pause()`/`resume()` taken into account.
+pub fn get_game_time_since_start(ctx: &Context) -> time::Duration {
+    ctx.timer_context.game_time_since_start
+}
+
+/// Sets the rate at which the game clock advances relative to the
+/// wall clock.  `1.0` is normal speed, `0.5` is half speed
+/// (slow-motion), `2.0` is double speed (fast-forward).  Does not
+/// affect `get_delta()`/`get_time_since_start()`, which always
+/// reflect real elapsed time; use `get_scaled_delta()` and
+/// `get_game_time_since_start()` for clock-scaled values.
+///
+/// Negative scales (and `NaN`) are clamped to `0.0`, the same as
+/// `pause()`, rather than running time backwards -- a negative
+/// `scaled_delta` would otherwise panic the `debug_assert!` in
+/// `f64_to_duration()` the next time `tick()` runs.
+pub fn set_time_scale(ctx: &mut Context, time_scale: f64) {
+    let time_scale = if time_scale >= 0.0 { time_scale } else { 0.0 };
+    ctx.timer_context.time_scale = time_scale;
+}
+
+/// Gets the current time scale; see `set_time_scale()`.
+pub fn get_time_scale(ctx: &Context) -> f64 {
+    ctx.timer_context.time_scale
+}
+
+/// Pauses the game clock.  While paused, `get_scaled_delta()` returns
+/// a zero duration and `get_game_time_since_start()` stops advancing,
+/// so anything driven off of them (simulation, animation) effectively
+/// freezes.
+///
+/// `tick()` feeds `residual_update_dt` from this same scaled delta, so
+/// pausing also freezes `check_update_time()` and the
+/// `Timestep::Fixed` auto-update loop driven by `event::run()` -- no
+/// fixed-step `update()` calls will fire again until `resume()` is
+/// called.  With `Timestep::Variable`, `event::run()` still calls
+/// `update()` once per frame while paused, and `get_scaled_delta()`
+/// reads zero in it.  Does not
+/// affect `get_delta()` or the real-time FPS tracking, which always
+/// reflect wall-clock time.
+pub fn pause(ctx: &mut Context) {
+    ctx.timer_context.paused = true;
+}
+
+/// Resumes the game clock after a call to `pause()`.
+pub fn resume(ctx: &mut Context) {
+    ctx.timer_context.paused = false;
+}
+
+/// Returns whether the game clock is currently paused.
+pub fn is_paused(ctx: &Context) -> bool {
+    ctx.timer_context.paused
+}
+
+/// Enables or disables adaptive frame pacing.
+///
+/// When enabled, `event::run()` will delay the start of each
+/// frame's input handling and simulation until shortly before the
+/// next vblank is predicted to occur, based on a rolling estimate of
+/// how long `draw()` and its present take.  This trades a small risk
+/// of missing vsync under a sudden spike for lower, more consistent
+/// input latency.  Disabled by default.
+pub fn set_frame_pacing(ctx: &mut Context, enabled: bool) {
+    ctx.timer_context.frame_pacing = enabled;
+}
+
+/// Returns whether adaptive frame pacing is currently enabled; see
+/// `set_frame_pacing()`.
+pub fn get_frame_pacing(ctx: &Context) -> bool {
+    ctx.timer_context.frame_pacing
+}
+
+/// Returns the current rolling estimate of how long `draw()` (plus
+/// its present) takes, as used by the frame pacer.
+pub fn get_estimated_draw_time(ctx: &Context) -> time::Duration {
+    ctx.timer_context.rolling_max_draw_time
+}
+
 /// This function will return true if the time since the
 /// last `update()` call has been equal to or greater to
 /// the update FPS indicated by the `target_fps`.
@@ -221,6 +527,21 @@ pub fn check_update_time(ctx: &mut Context, target_fps: u32) -> bool {
     }
 }
 
+/// Drains one `tick_rate`-sized chunk of `residual_update_dt`,
+/// returning whether an `update()` call should run.  This is what
+/// `event::run()` calls in a `while` loop to drive
+/// `Timestep::Fixed(updates_per_sec)`, the same way `check_update_time()`
+/// lets user code drive its own fixed-rate loop with a `target_fps`.
+pub(crate) fn drain_fixed_update_time(ctx: &mut Context, tick_rate: time::Duration) -> bool {
+    let timedata = &mut ctx.timer_context;
+    if timedata.residual_update_dt >= tick_rate {
+        timedata.residual_update_dt -= tick_rate;
+        true
+    } else {
+        false
+    }
+}
+
 /// Returns the fractional amount of a frame not consumed
 /// by  `check_update_time()`.  For example, if the desired
 /// update frame time is 40 ms (25 fps), and 45 ms have
@@ -236,6 +557,61 @@ pub fn get_remaining_update_time(ctx: &mut Context) -> time::Duration {
     ctx.timer_context.residual_update_dt
 }
 
+// The lowest update rate we'll honor for `Timestep::Fixed`.  Rates at
+// or below zero would turn `1.0 / updates_per_sec` into an infinite
+// or negative `tick_rate`, which panics the `debug_assert!` in
+// `f64_to_duration()` the first time `event::run()` computes it.
+const MIN_FIXED_UPDATES_PER_SEC: f64 = 1.0;
+
+/// Sets the `Timestep` mode used to drive `update()`/`draw()`.
+///
+/// With `Timestep::Fixed(updates_per_sec)`, `EventHandler::update()`
+/// will be called zero or more times per frame, each advancing the
+/// simulation by exactly `1.0 / updates_per_sec` seconds, and
+/// `draw()` will be called exactly once afterward. With
+/// `Timestep::Variable` (the default), `update()` is called once per
+/// frame with the real elapsed time.
+///
+/// `updates_per_sec` is clamped to `MIN_FIXED_UPDATES_PER_SEC` if it's
+/// given as `NaN` or anything below it, including zero and negative
+/// rates.
+pub fn set_timestep(ctx: &mut Context, timestep: Timestep) {
+    let timestep = match timestep {
+        Timestep::Fixed(updates_per_sec)
+            if updates_per_sec.is_nan() || updates_per_sec < MIN_FIXED_UPDATES_PER_SEC =>
+        {
+            Timestep::Fixed(MIN_FIXED_UPDATES_PER_SEC)
+        }
+        other => other,
+    };
+    ctx.timer_context.timestep = timestep;
+}
+
+/// Gets the `Timestep` mode currently in use.
+pub fn get_timestep(ctx: &Context) -> Timestep {
+    ctx.timer_context.timestep
+}
+
+/// Returns how far between the last two simulation steps we currently
+/// are, as a fraction from 0.0 to 1.0.
+///
+/// Only meaningful when running with `Timestep::Fixed`; intended to
+/// be used in `draw()` to interpolate render state between the
+/// previous and current simulation step, avoiding stutter when the
+/// update rate and the display's refresh rate don't line up. Returns
+/// `0.0` when running with `Timestep::Variable`.
+pub fn get_blend_factor(ctx: &Context) -> f64 {
+    let tc = &ctx.timer_context;
+    match tc.timestep {
+        Timestep::Fixed(updates_per_sec) => {
+            let tick_rate = 1.0 / updates_per_sec;
+            let residual = duration_to_f64(tc.residual_update_dt);
+            (residual / tick_rate).max(0.0).min(1.0)
+        }
+        Timestep::Variable => 0.0,
+    }
+}
+
 /// Pauses the current thread for the target duration.
 /// Just calls `std::thread::sleep()` so it's as accurate
 /// as that is (which is usually not very).